@@ -0,0 +1,31 @@
+use std::error;
+use std::fmt;
+
+/// Error carried by `StreamTimeoutError` when a stream stalls past its
+/// configured timeout. `StreamDeadline` reports its deadline through
+/// `DeadlineError` instead.
+///
+/// This is a zero-sized marker distinct from any error the underlying
+/// stream or the timer driver itself might produce, so callers can tell
+/// "timed out" apart from a genuine failure with a single `is_elapsed()`
+/// check instead of matching on crate-internal variants.
+#[derive(Debug)]
+pub struct Elapsed(());
+
+impl Elapsed {
+    pub(crate) fn new() -> Elapsed {
+        Elapsed(())
+    }
+}
+
+impl fmt::Display for Elapsed {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "stream stalled: timed out")
+    }
+}
+
+impl error::Error for Elapsed {
+    fn description(&self) -> &str {
+        "stream stalled: timed out"
+    }
+}
@@ -2,27 +2,30 @@ use {Delay, DeadlineError};
 
 use futures::{Stream, Future, Poll, Async};
 
-use std::time::{Instant, Duration};
+use std::time::Instant;
 
-
-/// Allows a given `Stream` to execute until it has not been ready for the duration
-/// of the timeout.
+/// Allows a given `Stream` to execute until a fixed, absolute instant is
+/// reached.
+///
+/// Unlike `StreamTimeout`, which re-arms on every item the underlying stream
+/// produces, `StreamDeadline`'s delay is set once at construction and never
+/// reset: it bounds the total lifetime of the stream, however many items
+/// arrive, rather than the gap between items.
 #[must_use = "streams do nothing unless polled"]
 #[derive(Debug)]
 pub struct StreamDeadline<T> {
     stream: T,
-    timeout: Duration,
     delay: Delay,
 }
 
 impl<T> StreamDeadline<T> {
-    /// Create a new `StreamDeadline` that completes when `stream` completes or when
-    /// `stream` hasn't been ready for the `timout` duration
-    pub fn new(stream: T, timeout: Duration) -> StreamDeadline<T> {
+    /// Create a new `StreamDeadline` that yields the items of `stream` until
+    /// it completes, or errors with `DeadlineError::elapsed()` once
+    /// `deadline` is reached, whichever happens first.
+    pub fn new(stream: T, deadline: Instant) -> StreamDeadline<T> {
         StreamDeadline {
             stream,
-            timeout,
-            delay: Delay::new(Instant::now() + timeout),
+            delay: Delay::new(deadline),
         }
     }
 
@@ -40,10 +43,6 @@ impl<T> StreamDeadline<T> {
     pub fn into_inner(self) -> T {
         self.stream
     }
-
-    fn reset_delay(&mut self) {
-        self.delay = Delay::new(Instant::now() + self.timeout);
-    }
 }
 
 impl<T> Stream for StreamDeadline<T>
@@ -53,26 +52,30 @@ where T: Stream,
     type Error = DeadlineError<T::Error>;
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        // First, try polling the future
-        match self.stream.poll() {
-            Ok(Async::Ready(v)) => {
-                // reset the delay when we receive a ready
-                self.reset_delay();
-                return Ok(Async::Ready(v))
-            },
+        // First, check whether the absolute deadline has passed, so that a
+        // stream which always has an item ready cannot run past it. The delay
+        // is never reset here: it tracks the absolute deadline for the whole
+        // stream, not the gap since the last item.
+        match self.delay.poll() {
             Ok(Async::NotReady) => {}
-            Err(e) => return Err(DeadlineError::inner(e)),
+            Ok(Async::Ready(_)) => return Err(DeadlineError::elapsed()),
+            Err(e) => return Err(DeadlineError::timer(e)),
         }
 
-        // Now check the timer
-        match self.delay.poll() {
-            Ok(Async::NotReady) => Ok(Async::NotReady),
-            Ok(Async::Ready(_)) => {
-                // Send none to signal that there hasn't been a ready poll
-                // after the timeout so we can probably stop.
-                Ok(Async::Ready(None))
-            },
-            Err(e) => Err(DeadlineError::timer(e)),
-        }
+        // Then poll the stream. `?` converts a `T::Error` into a
+        // `DeadlineError<T::Error>` via `DeadlineError`'s own `From` impl,
+        // mirroring how `StreamTimeout::poll` uses `StreamTimeoutError`'s.
+        Ok(self.stream.poll()?)
+    }
+}
+
+// Lets `self.stream.poll()?` above convert a `T::Error` straight into a
+// `DeadlineError<T::Error>`, the same `From<T::Error>` bound chunk0-2 added
+// for `StreamTimeoutError`. `DeadlineError` is shared with the plain
+// `Deadline<Future>` combinator, so its `Kind`/`Elapsed` representation
+// stays as-is here -- only this conversion is new.
+impl<T> From<T> for DeadlineError<T> {
+    fn from(err: T) -> DeadlineError<T> {
+        DeadlineError::inner(err)
     }
 }
@@ -1,10 +1,11 @@
-use Delay;
+use {Delay, Elapsed};
+use clock::now;
 
 use futures::{Stream, Future, Poll, Async};
 
 use std::error;
 use std::fmt;
-use std::time::{Instant, Duration};
+use std::time::Duration;
 
 /// Allows a given `Stream` to execute until it has not been ready for the duration
 /// of the timeout.
@@ -14,6 +15,7 @@ pub struct StreamTimeout<T> {
     stream: T,
     timeout: Duration,
     delay: Delay,
+    continue_on_timeout: bool,
 }
 
 /// Error returned by `StreamTimeout` stream.
@@ -28,6 +30,10 @@ enum Kind<T> {
 
     /// Timer returned an error.
     Timer(::Error),
+
+    /// The stream went idle for the timeout duration. Only produced when
+    /// the `StreamTimeout` was built with `continue_on_timeout()`.
+    Elapsed(Elapsed),
 }
 
 impl<T> StreamTimeout<T> {
@@ -37,10 +43,27 @@ impl<T> StreamTimeout<T> {
         StreamTimeout {
             stream,
             timeout,
-            delay: Delay::new(Instant::now() + timeout),
+            delay: Delay::new(now() + timeout),
+            continue_on_timeout: false,
         }
     }
 
+    /// Causes this `StreamTimeout` to keep polling the underlying stream after an
+    /// idle timeout instead of ending it.
+    ///
+    /// Normally, once the stream has not produced an item within the timeout
+    /// duration, `poll` returns `Ok(Async::Ready(None))` and the stream is
+    /// considered finished. When this mode is enabled, an idle timeout instead
+    /// yields a `StreamTimeoutError::elapsed()` for that polling interval, the
+    /// delay is reset, and the underlying stream keeps being polled on
+    /// subsequent calls. This is useful for long-lived streams, such as a
+    /// heartbeat or SSE feed, where a consumer wants to observe "no item within
+    /// N seconds" without tearing the stream down.
+    pub fn continue_on_timeout(mut self) -> StreamTimeout<T> {
+        self.continue_on_timeout = true;
+        self
+    }
+
     /// Gets a reference to the underlying stream in this deadline.
     pub fn get_ref(&self) -> &T {
         &self.stream
@@ -57,7 +80,7 @@ impl<T> StreamTimeout<T> {
     }
 
     fn reset_delay(&mut self) {
-        self.delay = Delay::new(Instant::now() + self.timeout);
+        self.delay = Delay::new(now() + self.timeout);
     }
 }
 
@@ -68,24 +91,28 @@ where T: Stream,
     type Error = StreamTimeoutError<T::Error>;
 
     fn poll(&mut self) -> Poll<Option<Self::Item>, Self::Error> {
-        // First, try polling the future
-        match self.stream.poll() {
-            Ok(Async::Ready(v)) => {
-                // reset the delay when we receive a ready
-                self.reset_delay();
-                return Ok(Async::Ready(v))
-            },
-            Ok(Async::NotReady) => {}
-            Err(e) => return Err(StreamTimeoutError::inner(e)),
+        // First, try polling the future. `?` converts a `T::Error` into a
+        // `StreamTimeoutError<T::Error>` via the blanket `From` impl below.
+        if let Async::Ready(v) = self.stream.poll()? {
+            // reset the delay when we receive a ready
+            self.reset_delay();
+            return Ok(Async::Ready(v));
         }
 
         // Now check the timer
         match self.delay.poll() {
             Ok(Async::NotReady) => Ok(Async::NotReady),
             Ok(Async::Ready(_)) => {
-                // Send none to signal that there hasn't been a ready poll
-                // after the timeout so we can probably stop.
-                Ok(Async::Ready(None))
+                if self.continue_on_timeout {
+                    // Let the caller know this interval went idle, but keep
+                    // the underlying stream alive for the next poll.
+                    self.reset_delay();
+                    Err(StreamTimeoutError::elapsed())
+                } else {
+                    // Send none to signal that there hasn't been a ready poll
+                    // after the timeout so we can probably stop.
+                    Ok(Async::Ready(None))
+                }
             },
             Err(e) => Err(StreamTimeoutError::timer(e)),
         }
@@ -139,6 +166,36 @@ impl<T> StreamTimeoutError<T> {
             _ => None,
         }
     }
+
+    /// Creates a new `StreamTimeoutError` representing the stream going idle
+    /// for the timeout duration, without the stream itself having ended.
+    pub fn elapsed() -> StreamTimeoutError<T> {
+        StreamTimeoutError(Kind::Elapsed(Elapsed::new()))
+    }
+
+    /// Returns `true` if the error was caused by the stream going idle for the
+    /// timeout duration.
+    pub fn is_elapsed(&self) -> bool {
+        match self.0 {
+            Kind::Elapsed(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Consumes `self`, returning the `Elapsed` error, if that's what
+    /// occurred.
+    pub fn into_elapsed(self) -> Option<Elapsed> {
+        match self.0 {
+            Kind::Elapsed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl<T> From<T> for StreamTimeoutError<T> {
+    fn from(err: T) -> StreamTimeoutError<T> {
+        StreamTimeoutError::inner(err)
+    }
 }
 
 impl<T: error::Error> error::Error for StreamTimeoutError<T> {
@@ -148,6 +205,7 @@ impl<T: error::Error> error::Error for StreamTimeoutError<T> {
         match self.0 {
             Inner(ref e) => e.description(),
             Timer(ref e) => e.description(),
+            Elapsed(ref e) => error::Error::description(e),
         }
     }
 }
@@ -159,6 +217,53 @@ impl<T: fmt::Display> fmt::Display for StreamTimeoutError<T> {
         match self.0 {
             Inner(ref e) => e.fmt(fmt),
             Timer(ref e) => e.fmt(fmt),
+            Elapsed(ref e) => e.fmt(fmt),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A stream that never produces an item, so the only thing that can
+    /// make a `StreamTimeout` wrapping it progress is its idle timer.
+    struct Idle;
+
+    impl Stream for Idle {
+        type Item = ();
+        type Error = ();
+
+        fn poll(&mut self) -> Poll<Option<()>, ()> {
+            Ok(Async::NotReady)
+        }
+    }
+
+    #[test]
+    fn continue_on_timeout_survives_idle_periods_and_keeps_polling() {
+        // `tokio_test::clock::mock` installs a frozen `Clock` (read by the
+        // crate's timer wheel via `clock::now()`) and hands back a handle
+        // that turns the timer when advanced, so this runs instantly
+        // instead of sleeping through two real timeouts.
+        ::tokio_test::clock::mock(|handle| {
+            let mut timeout = StreamTimeout::new(Idle, Duration::from_secs(20))
+                .continue_on_timeout();
+
+            assert!(timeout.poll().unwrap().is_not_ready());
+
+            handle.advance(Duration::from_secs(20));
+            match timeout.poll() {
+                Err(ref e) if e.is_elapsed() => {}
+                other => panic!("expected first elapsed, got {:?}", other),
+            }
+
+            // Observe a second idle timeout: if the stream were torn down
+            // after the first, it would never arrive.
+            handle.advance(Duration::from_secs(20));
+            match timeout.poll() {
+                Err(ref e) if e.is_elapsed() => {}
+                other => panic!("expected second elapsed, got {:?}", other),
+            }
+        });
+    }
+}